@@ -2,7 +2,6 @@
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Write, BufReader, BufWriter};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
 use chrono::{DateTime, Local};
 use regex::Regex;
 
@@ -16,7 +15,9 @@ enum BackupError {
     FileNotFound(String),
     IoError(io::Error),
     PathTraversal(String),
-    PermissionDenied(String),
+    Locked(String),
+    SymlinkRejected(String),
+    IntegrityMismatch { expected: String, actual: String },
 }
 
 impl std::fmt::Display for BackupError {
@@ -26,7 +27,13 @@ impl std::fmt::Display for BackupError {
             BackupError::FileNotFound(msg) => write!(f, "File not found: {}", msg),
             BackupError::IoError(err) => write!(f, "IO Error: {}", err),
             BackupError::PathTraversal(msg) => write!(f, "Path traversal attempt: {}", msg),
-            BackupError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            BackupError::Locked(msg) => write!(f, "Resource locked: {}", msg),
+            BackupError::SymlinkRejected(msg) => write!(f, "Symlink rejected: {}", msg),
+            BackupError::IntegrityMismatch { expected, actual } => write!(
+                f,
+                "Integrity mismatch: expected {}, got {}",
+                expected, actual
+            ),
         }
     }
 }
@@ -37,6 +44,444 @@ impl From<io::Error> for BackupError {
     }
 }
 
+/// RAII guard around a sidecar `<filename>.lock` file holding an exclusive
+/// advisory lock for the duration of a backup/restore. The lock is released
+/// when the guard is dropped. The lock file itself is intentionally left on
+/// disk: unlinking it on drop would let a third process create a fresh inode
+/// and lock that while a second process still holds the old one, so two
+/// backups could run at once and tear the `.bak`.
+struct BackupLock {
+    file: File,
+}
+
+impl BackupLock {
+    /// Acquire an exclusive advisory lock on `<filename>.lock`. The lock is
+    /// taken non-blocking, so `BackupError::Locked` is returned immediately if
+    /// another process already holds it (or the platform refuses the lock)
+    /// rather than waiting indefinitely.
+    fn acquire(filename: &str) -> BackupResult<BackupLock> {
+        let path = PathBuf::from(format!("{}.lock", filename));
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&path)?;
+
+        platform_lock::lock_exclusive(&file).map_err(|e| {
+            BackupError::Locked(format!(
+                "another backup is in progress on {} ({})",
+                filename, e
+            ))
+        })?;
+
+        Ok(BackupLock { file })
+    }
+}
+
+impl Drop for BackupLock {
+    fn drop(&mut self) {
+        // Best effort: release the flock but keep the lock file in place. A
+        // failure here is non-fatal and would only matter while unwinding.
+        let _ = platform_lock::unlock(&self.file);
+    }
+}
+
+/// Platform primitives for exclusive advisory locking on an open file handle.
+#[cfg(unix)]
+mod platform_lock {
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+
+    const LOCK_EX: i32 = 2;
+    const LOCK_NB: i32 = 4;
+    const LOCK_UN: i32 = 8;
+
+    extern "C" {
+        fn flock(fd: i32, operation: i32) -> i32;
+    }
+
+    pub fn lock_exclusive(file: &File) -> io::Result<()> {
+        // SAFETY: `fd` is a valid descriptor borrowed from `file` for the
+        // duration of the call. LOCK_NB makes contention fail fast (EWOULDBLOCK)
+        // instead of blocking, so the caller can report it as `Locked`.
+        let rc = unsafe { flock(file.as_raw_fd(), LOCK_EX | LOCK_NB) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub fn unlock(file: &File) -> io::Result<()> {
+        let rc = unsafe { flock(file.as_raw_fd(), LOCK_UN) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform_lock {
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+
+    const LOCKFILE_FAIL_IMMEDIATELY: u32 = 0x0000_0001;
+    const LOCKFILE_EXCLUSIVE_LOCK: u32 = 0x0000_0002;
+
+    #[repr(C)]
+    struct Overlapped {
+        internal: usize,
+        internal_high: usize,
+        offset: u32,
+        offset_high: u32,
+        h_event: *mut core::ffi::c_void,
+    }
+
+    extern "system" {
+        fn LockFileEx(
+            handle: *mut core::ffi::c_void,
+            flags: u32,
+            reserved: u32,
+            bytes_low: u32,
+            bytes_high: u32,
+            overlapped: *mut Overlapped,
+        ) -> i32;
+        fn UnlockFile(
+            handle: *mut core::ffi::c_void,
+            offset_low: u32,
+            offset_high: u32,
+            bytes_low: u32,
+            bytes_high: u32,
+        ) -> i32;
+    }
+
+    pub fn lock_exclusive(file: &File) -> io::Result<()> {
+        let mut overlapped = Overlapped {
+            internal: 0,
+            internal_high: 0,
+            offset: 0,
+            offset_high: 0,
+            h_event: core::ptr::null_mut(),
+        };
+        let rc = unsafe {
+            LockFileEx(
+                file.as_raw_handle() as *mut _,
+                LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY,
+                0,
+                !0,
+                !0,
+                &mut overlapped,
+            )
+        };
+        if rc != 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub fn unlock(file: &File) -> io::Result<()> {
+        let rc = unsafe { UnlockFile(file.as_raw_handle() as *mut _, 0, 0, !0, !0) };
+        if rc != 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+/// Platform primitives for carrying a source file's permission bits and
+/// timestamps over to a freshly written destination (the `.bak` during
+/// backup, the restored original during restore).
+#[cfg(unix)]
+mod platform_meta {
+    use std::ffi::CString;
+    use std::fs::{self, Metadata, Permissions};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use std::path::Path;
+
+    const AT_FDCWD: i32 = -100;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: i64,
+        tv_nsec: i64,
+    }
+
+    extern "C" {
+        fn utimensat(dirfd: i32, path: *const i8, times: *const Timespec, flags: i32) -> i32;
+    }
+
+    /// The raw Unix mode bits of `meta`, as written to the log entry and to the
+    /// directory manifest (which `restore_dir` reads back).
+    pub fn mode_of(meta: &Metadata) -> u32 {
+        meta.mode()
+    }
+
+    /// Apply `src`'s mode and access/modification times to `dest`.
+    pub fn apply(src: &Metadata, dest: &Path) -> io::Result<()> {
+        fs::set_permissions(dest, Permissions::from_mode(src.mode()))?;
+
+        let times = [
+            Timespec { tv_sec: src.atime(), tv_nsec: src.atime_nsec() },
+            Timespec { tv_sec: src.mtime(), tv_nsec: src.mtime_nsec() },
+        ];
+        let c_path = CString::new(dest.as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains NUL"))?;
+        // SAFETY: `c_path` outlives the call and `times` points at two valid
+        // timespec entries as utimensat expects.
+        let rc = unsafe { utimensat(AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    /// Apply a raw mode value (as recorded in a manifest) to `dest`.
+    pub fn set_mode(dest: &Path, mode: u32) -> io::Result<()> {
+        fs::set_permissions(dest, Permissions::from_mode(mode))
+    }
+}
+
+#[cfg(not(unix))]
+mod platform_meta {
+    use std::fs::{self, Metadata};
+    use std::io;
+    use std::path::Path;
+
+    /// Without Unix mode bits, encode only the read-only flag as a POSIX-style
+    /// mode so the log entry stays uniform across platforms.
+    pub fn mode_of(meta: &Metadata) -> u32 {
+        if meta.permissions().readonly() {
+            0o444
+        } else {
+            0o644
+        }
+    }
+
+    /// The best a non-Unix target can do is carry the read-only flag over;
+    /// timestamps are left at their creation defaults.
+    pub fn apply(src: &Metadata, dest: &Path) -> io::Result<()> {
+        let mut perms = fs::metadata(dest)?.permissions();
+        perms.set_readonly(src.permissions().readonly());
+        fs::set_permissions(dest, perms)
+    }
+
+    /// Apply a recorded mode to `dest`, honouring only the owner-write bit.
+    pub fn set_mode(dest: &Path, mode: u32) -> io::Result<()> {
+        let mut perms = fs::metadata(dest)?.permissions();
+        perms.set_readonly(mode & 0o200 == 0);
+        fs::set_permissions(dest, perms)
+    }
+}
+
+/// Minimal streaming SHA-256, vendored so integrity checks carry no extra
+/// dependency. Feed bytes with `update`, then take the 32-byte digest with
+/// `finalize`.
+mod sha256 {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    pub struct Sha256 {
+        state: [u32; 8],
+        len: u64,
+        buf: [u8; 64],
+        buf_len: usize,
+    }
+
+    impl Sha256 {
+        pub fn new() -> Sha256 {
+            Sha256 {
+                state: [
+                    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c,
+                    0x1f83d9ab, 0x5be0cd19,
+                ],
+                len: 0,
+                buf: [0u8; 64],
+                buf_len: 0,
+            }
+        }
+
+        pub fn update(&mut self, mut data: &[u8]) {
+            self.len = self.len.wrapping_add(data.len() as u64);
+            if self.buf_len > 0 {
+                let need = 64 - self.buf_len;
+                let take = need.min(data.len());
+                self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+                self.buf_len += take;
+                data = &data[take..];
+                if self.buf_len == 64 {
+                    let block = self.buf;
+                    self.compress(&block);
+                    self.buf_len = 0;
+                }
+            }
+            while data.len() >= 64 {
+                let mut block = [0u8; 64];
+                block.copy_from_slice(&data[..64]);
+                self.compress(&block);
+                data = &data[64..];
+            }
+            if !data.is_empty() {
+                self.buf[..data.len()].copy_from_slice(data);
+                self.buf_len = data.len();
+            }
+        }
+
+        pub fn finalize(mut self) -> [u8; 32] {
+            let bit_len = self.len.wrapping_mul(8);
+            let n = self.buf_len;
+
+            // Append the mandatory 0x80 byte.
+            self.buf[n] = 0x80;
+            if n + 1 > 56 {
+                // No room for the length in this block: zero-fill and flush it.
+                for b in self.buf[n + 1..64].iter_mut() {
+                    *b = 0;
+                }
+                let block = self.buf;
+                self.compress(&block);
+                self.buf = [0u8; 64];
+            } else {
+                for b in self.buf[n + 1..56].iter_mut() {
+                    *b = 0;
+                }
+            }
+            // Append the 64-bit big-endian message length and compress.
+            self.buf[56..64].copy_from_slice(&bit_len.to_be_bytes());
+            let block = self.buf;
+            self.compress(&block);
+
+            let mut out = [0u8; 32];
+            for (i, word) in self.state.iter().enumerate() {
+                out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+            }
+            out
+        }
+
+        fn compress(&mut self, block: &[u8; 64]) {
+            let mut w = [0u32; 64];
+            for i in 0..16 {
+                w[i] = u32::from_be_bytes([
+                    block[i * 4],
+                    block[i * 4 + 1],
+                    block[i * 4 + 2],
+                    block[i * 4 + 3],
+                ]);
+            }
+            for i in 16..64 {
+                let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+                let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+                w[i] = w[i - 16]
+                    .wrapping_add(s0)
+                    .wrapping_add(w[i - 7])
+                    .wrapping_add(s1);
+            }
+
+            let mut h = self.state;
+            for i in 0..64 {
+                let s1 = h[4].rotate_right(6) ^ h[4].rotate_right(11) ^ h[4].rotate_right(25);
+                let ch = (h[4] & h[5]) ^ ((!h[4]) & h[6]);
+                let t1 = h[7]
+                    .wrapping_add(s1)
+                    .wrapping_add(ch)
+                    .wrapping_add(K[i])
+                    .wrapping_add(w[i]);
+                let s0 = h[0].rotate_right(2) ^ h[0].rotate_right(13) ^ h[0].rotate_right(22);
+                let maj = (h[0] & h[1]) ^ (h[0] & h[2]) ^ (h[1] & h[2]);
+                let t2 = s0.wrapping_add(maj);
+                h[7] = h[6];
+                h[6] = h[5];
+                h[5] = h[4];
+                h[4] = h[3].wrapping_add(t1);
+                h[3] = h[2];
+                h[2] = h[1];
+                h[1] = h[0];
+                h[0] = t1.wrapping_add(t2);
+            }
+            for (s, hi) in self.state.iter_mut().zip(h.iter()) {
+                *s = s.wrapping_add(*hi);
+            }
+        }
+    }
+
+    /// Lower-case hex encoding of a digest.
+    pub fn to_hex(digest: &[u8; 32]) -> String {
+        let mut s = String::with_capacity(64);
+        for byte in digest {
+            s.push_str(&format!("{:02x}", byte));
+        }
+        s
+    }
+}
+
+/// Reader adapter that feeds every byte it yields into a SHA-256 hasher, so a
+/// single `io::copy` both copies and hashes without a second pass over the file.
+struct HashingReader<R: Read> {
+    inner: R,
+    hasher: sha256::Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> HashingReader<R> {
+        HashingReader {
+            inner,
+            hasher: sha256::Sha256::new(),
+        }
+    }
+
+    fn digest(self) -> [u8; 32] {
+        self.hasher.finalize()
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Re-hash an existing `.bak` and compare it against the digest recorded in its
+/// `.sha256` sidecar, returning `IntegrityMismatch` if they differ.
+fn verify_backup(backup_name: &str) -> BackupResult<String> {
+    let sidecar = format!("{}.sha256", backup_name);
+    let stored = fs::read_to_string(&sidecar)?;
+    let expected = stored
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_string();
+
+    let file = File::open(backup_name)?;
+    let mut reader = HashingReader::new(BufReader::new(file));
+    io::copy(&mut reader, &mut io::sink())?;
+    let actual = sha256::to_hex(&reader.digest());
+
+    if actual != expected {
+        return Err(BackupError::IntegrityMismatch { expected, actual });
+    }
+    Ok(actual)
+}
+
 /// Secure logging function with timestamps
 fn log_action(action: &str) -> BackupResult<()> {
     let mut file = OpenOptions::new()
@@ -82,8 +527,46 @@ fn validate_filename(filename: &str) -> BackupResult<()> {
     Ok(())
 }
 
+/// Rejects a path whose final component is a symlink, closing the
+/// traversal bypass where a name made of otherwise-valid characters points at
+/// a file outside the working directory.
+///
+/// With `follow_symlinks` set the link is instead canonicalized and accepted
+/// only if it stays within the current working directory.
+fn check_symlink(path: &Path, follow_symlinks: bool) -> BackupResult<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if !meta.file_type().is_symlink() {
+        return Ok(());
+    }
+
+    if !follow_symlinks {
+        log_action(&format!(
+            "Security: symlink rejected - {}",
+            path.display()
+        ))?;
+        return Err(BackupError::SymlinkRejected(path.display().to_string()));
+    }
+
+    // Opt-in: follow the link but require the target to stay inside the cwd.
+    let target = fs::canonicalize(path)?;
+    let cwd = std::env::current_dir()?;
+    if !target.starts_with(&cwd) {
+        log_action(&format!(
+            "Security: symlink escapes working directory - {} -> {}",
+            path.display(),
+            target.display()
+        ))?;
+        return Err(BackupError::SymlinkRejected(format!(
+            "{} escapes working directory",
+            path.display()
+        )));
+    }
+
+    Ok(())
+}
+
 /// Creates a secure backup of the specified file
-fn backup_file(filename: &str) -> BackupResult<()> {
+fn backup_file(filename: &str, follow_symlinks: bool) -> BackupResult<()> {
     // Validate filename first
     validate_filename(filename)?;
     
@@ -94,6 +577,9 @@ fn backup_file(filename: &str) -> BackupResult<()> {
         return Err(BackupError::FileNotFound(filename.to_string()));
     }
     
+    // Refuse symlinks before is_file(), which would follow them.
+    check_symlink(source_path, follow_symlinks)?;
+
     // Check if we can read the file
     if !source_path.is_file() {
         log_action(&format!("Backup failed: Not a regular file - {}", filename))?;
@@ -102,26 +588,50 @@ fn backup_file(filename: &str) -> BackupResult<()> {
         ));
     }
     
+    // Serialize against other SafeBackup processes working on the same file.
+    let _lock = BackupLock::acquire(filename)?;
+
     // Create backup filename
     let backup_name = format!("{}.bak", filename);
-    
-    // Perform the backup using secure binary copy
+
+    // Drop any previous .bak first: the mode we carry over below can leave it
+    // read-only (e.g. a 0400 source), and re-creating a read-only file with
+    // O_TRUNC would fail with EACCES for any user but root on the next backup.
+    if Path::new(&backup_name).exists() {
+        fs::remove_file(&backup_name)?;
+    }
+
+    // Perform the backup using secure binary copy, hashing the bytes as they
+    // stream through so the file is read only once.
     let source_file = File::open(filename)?;
-    let mut reader = BufReader::new(source_file);
-    
+    let mut reader = HashingReader::new(BufReader::new(source_file));
+
     let dest_file = File::create(&backup_name)?;
     let mut writer = BufWriter::new(dest_file);
-    
+
     // Copy file contents
     let bytes_copied = io::copy(&mut reader, &mut writer)?;
-    
+
     // Ensure all data is written
     writer.flush()?;
-    
+    drop(writer);
+
+    // Record the digest and byte count so a restore (or `verify`) can detect
+    // bit rot or tampering in the .bak.
+    let digest = sha256::to_hex(&reader.digest());
+    let checksum_name = format!("{}.sha256", backup_name);
+    fs::write(&checksum_name, format!("{}  {}\n", digest, bytes_copied))?;
+
+    // Carry the source's permissions and timestamps onto the backup so a
+    // sensitive file's .bak is not left world-readable.
+    let source_meta = fs::metadata(filename)?;
+    let mode = platform_meta::mode_of(&source_meta);
+    platform_meta::apply(&source_meta, Path::new(&backup_name))?;
+
     println!("Your backup created: {}", backup_name);
     log_action(&format!(
-        "Backup successful: {} -> {} ({} bytes)", 
-        filename, backup_name, bytes_copied
+        "Backup successful: {} -> {} ({} bytes, mode {:o})",
+        filename, backup_name, bytes_copied, mode
     ))?;
     
     Ok(())
@@ -132,6 +642,9 @@ fn restore_file(filename: &str) -> BackupResult<()> {
     // Validate filename
     validate_filename(filename)?;
     
+    // Serialize against other SafeBackup processes working on the same file.
+    let _lock = BackupLock::acquire(filename)?;
+
     // Create backup filename
     let backup_name = format!("{}.bak", filename);
     let backup_path = Path::new(&backup_name);
@@ -141,40 +654,275 @@ fn restore_file(filename: &str) -> BackupResult<()> {
         log_action(&format!("Restore failed: Backup not found - {}", backup_name))?;
         return Err(BackupError::FileNotFound(backup_name));
     }
-    
-    // Perform the restoration
+
+    // Verify the backup against its stored digest before overwriting the
+    // original, so a corrupted .bak never clobbers good data.
+    if Path::new(&format!("{}.sha256", backup_name)).exists() {
+        if let Err(e) = verify_backup(&backup_name) {
+            log_action(&format!("Restore aborted: integrity check failed - {}", e))?;
+            return Err(e);
+        }
+    }
+
+    // Perform the restoration. Remove a read-only original first so the
+    // O_TRUNC in File::create does not fail with EACCES when recovering over a
+    // file whose preserved mode left it non-writable.
+    if Path::new(filename).exists() {
+        fs::remove_file(filename)?;
+    }
+
     let source_file = File::open(&backup_name)?;
     let mut reader = BufReader::new(source_file);
-    
+
     let dest_file = File::create(filename)?;
     let mut writer = BufWriter::new(dest_file);
     
     // Copy file contents
     let bytes_copied = io::copy(&mut reader, &mut writer)?;
-    
+
     // Ensure all data is written
     writer.flush()?;
-    
+    drop(writer);
+
+    // Restore the backup's permissions and timestamps onto the recovered file.
+    let backup_meta = fs::metadata(&backup_name)?;
+    let mode = platform_meta::mode_of(&backup_meta);
+    platform_meta::apply(&backup_meta, Path::new(filename))?;
+
     println!("File restored from: {}", backup_name);
     log_action(&format!(
-        "Restore successful: {} -> {} ({} bytes)", 
-        backup_name, filename, bytes_copied
+        "Restore successful: {} -> {} ({} bytes, mode {:o})",
+        backup_name, filename, bytes_copied, mode
     ))?;
     
     Ok(())
 }
 
+/// Recursively backs up a directory tree into a mirrored `<name>.bak/` tree,
+/// writing a manifest that records the type and mode of every entry so the
+/// tree can be faithfully reconstructed on restore.
+fn backup_dir(dirname: &str, follow_symlinks: bool) -> BackupResult<()> {
+    validate_filename(dirname)?;
+
+    let root = Path::new(dirname);
+    check_symlink(root, follow_symlinks)?;
+    if !root.is_dir() {
+        return Err(BackupError::InvalidFilename(
+            "Target is not a directory".to_string(),
+        ));
+    }
+
+    let _lock = BackupLock::acquire(dirname)?;
+
+    let backup_root = PathBuf::from(format!("{}.bak", dirname));
+    fs::create_dir_all(&backup_root)?;
+
+    let manifest_name = format!("{}.bak.manifest", dirname);
+    let mut manifest = BufWriter::new(File::create(&manifest_name)?);
+
+    // Manual stack-based walk. Directory entries are written before their
+    // contents, giving the restore a ready-to-replay top-down ordering.
+    let mut stack = vec![root.to_path_buf()];
+    let mut files = 0u64;
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            let file_type = entry.file_type()?;
+            let dest = backup_root.join(rel);
+
+            // The manifest is tab- and newline-delimited with no escaping, so
+            // an entry name carrying either byte would corrupt it and mis-restore
+            // a later entry. Reject such names rather than silently misparse.
+            let rel_str = rel.to_string_lossy();
+            if rel_str.contains('\t') || rel_str.contains('\n') {
+                log_action(&format!(
+                    "Security: entry name with tab/newline rejected - {}",
+                    rel.display()
+                ))?;
+                return Err(BackupError::InvalidFilename(format!(
+                    "entry name contains a tab or newline: {}",
+                    rel.display()
+                )));
+            }
+
+            if file_type.is_symlink() {
+                // Symlinks are recorded but neither copied nor dereferenced;
+                // restore_dir does not recreate them.
+                writeln!(manifest, "symlink\t0\t{}", rel.display())?;
+                log_action(&format!("Backup: symlink recorded (skipped) - {}", rel.display()))?;
+            } else if file_type.is_dir() {
+                fs::create_dir_all(&dest)?;
+                let meta = fs::symlink_metadata(&path)?;
+                let mode = platform_meta::mode_of(&meta);
+                writeln!(manifest, "dir\t{:o}\t{}", mode, rel.display())?;
+                log_action(&format!("Backup: directory - {}", rel.display()))?;
+                stack.push(path);
+            } else {
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                // Drop a stale .bak entry first: a previously copied file may
+                // carry a read-only source mode, and O_TRUNC over it would fail
+                // with EACCES for any user but root on a second backup.
+                if dest.exists() {
+                    fs::remove_file(&dest)?;
+                }
+                let source_file = File::open(&path)?;
+                let mut reader = BufReader::new(source_file);
+                let dest_file = File::create(&dest)?;
+                let mut writer = BufWriter::new(dest_file);
+                io::copy(&mut reader, &mut writer)?;
+                writer.flush()?;
+                drop(writer);
+
+                let meta = fs::metadata(&path)?;
+                let mode = platform_meta::mode_of(&meta);
+                platform_meta::apply(&meta, &dest)?;
+                writeln!(manifest, "file\t{:o}\t{}", mode, rel.display())?;
+                log_action(&format!("Backup: file - {}", rel.display()))?;
+                files += 1;
+            }
+        }
+    }
+    manifest.flush()?;
+
+    println!("Your directory backup created: {}/", backup_root.display());
+    log_action(&format!(
+        "Backup successful: {} -> {}/ ({} files)",
+        dirname,
+        backup_root.display(),
+        files
+    ))?;
+
+    Ok(())
+}
+
+/// Restores a directory tree from its `<name>.bak/` mirror, replaying the
+/// manifest so directories are recreated before their contents and every
+/// entry regains the mode captured during backup.
+fn restore_dir(dirname: &str) -> BackupResult<()> {
+    validate_filename(dirname)?;
+
+    let manifest_name = format!("{}.bak.manifest", dirname);
+    if !Path::new(&manifest_name).exists() {
+        return Err(BackupError::FileNotFound(manifest_name));
+    }
+
+    let _lock = BackupLock::acquire(dirname)?;
+
+    let backup_root = PathBuf::from(format!("{}.bak", dirname));
+    let root = PathBuf::from(dirname);
+    fs::create_dir_all(&root)?;
+
+    // Directory modes are deferred to a second pass: applying a restrictive
+    // mode (e.g. 0o555) the moment a directory is created would make it
+    // non-writable before its files are restored, so File::create for each
+    // child would fail with EACCES for any user but root.
+    let mut dir_modes: Vec<(PathBuf, u32)> = Vec::new();
+
+    let manifest = fs::read_to_string(&manifest_name)?;
+    for line in manifest.lines() {
+        let mut fields = line.splitn(3, '\t');
+        let kind = fields.next().unwrap_or("");
+        let mode = fields
+            .next()
+            .and_then(|m| u32::from_str_radix(m, 8).ok())
+            .unwrap_or(0);
+        let rel = match fields.next() {
+            Some(rel) => rel,
+            None => continue,
+        };
+        let dest = root.join(rel);
+
+        match kind {
+            "dir" => {
+                fs::create_dir_all(&dest)?;
+                dir_modes.push((dest, mode));
+                log_action(&format!("Restore: directory - {}", rel))?;
+            }
+            "file" => {
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                // Drop a read-only restored file first so O_TRUNC does not fail
+                // with EACCES on a re-restore, matching restore_file.
+                if dest.exists() {
+                    fs::remove_file(&dest)?;
+                }
+                let source_file = File::open(backup_root.join(rel))?;
+                let mut reader = BufReader::new(source_file);
+                let dest_file = File::create(&dest)?;
+                let mut writer = BufWriter::new(dest_file);
+                io::copy(&mut reader, &mut writer)?;
+                writer.flush()?;
+                drop(writer);
+                platform_meta::set_mode(&dest, mode)?;
+                log_action(&format!("Restore: file - {}", rel))?;
+            }
+            "symlink" => {
+                log_action(&format!("Restore: symlink recorded (skipped) - {}", rel))?;
+            }
+            _ => {}
+        }
+    }
+
+    // Second pass: now that every file is in place, apply the captured
+    // directory modes. Deepest paths first so a parent made read-only never
+    // blocks a chmod on its children.
+    dir_modes.sort_by(|a, b| b.0.as_os_str().len().cmp(&a.0.as_os_str().len()));
+    for (dest, mode) in &dir_modes {
+        platform_meta::set_mode(dest, *mode)?;
+    }
+
+    println!("Directory restored from: {}/", backup_root.display());
+    log_action(&format!("Restore successful: {}/ -> {}", backup_root.display(), dirname))?;
+
+    Ok(())
+}
+
+/// Checks a file's backup against its stored checksum without restoring it.
+fn verify_file(filename: &str) -> BackupResult<()> {
+    validate_filename(filename)?;
+
+    let backup_name = format!("{}.bak", filename);
+    if !Path::new(&backup_name).exists() {
+        return Err(BackupError::FileNotFound(backup_name));
+    }
+    if !Path::new(&format!("{}.sha256", backup_name)).exists() {
+        return Err(BackupError::FileNotFound(format!("{}.sha256", backup_name)));
+    }
+
+    match verify_backup(&backup_name) {
+        Ok(digest) => {
+            println!("Backup verified: {} ({})", backup_name, digest);
+            log_action(&format!("Verify successful: {} ({})", backup_name, digest))?;
+            Ok(())
+        }
+        Err(e) => {
+            log_action(&format!("Verify failed: {} - {}", backup_name, e))?;
+            Err(e)
+        }
+    }
+}
+
 /// Securely deletes a file after confirmation
-fn delete_file(filename: &str) -> BackupResult<()> {
+fn delete_file(filename: &str, follow_symlinks: bool) -> BackupResult<()> {
     // Validate filename
     validate_filename(filename)?;
-    
+
     // Check if file exists
     let file_path = Path::new(filename);
     if !file_path.exists() {
         log_action(&format!("Delete failed: File not found - {}", filename))?;
         return Err(BackupError::FileNotFound(filename.to_string()));
     }
+
+    // Refuse to delete a symlink unless --follow-symlinks is set and it
+    // resolves inside the working directory; remove_file then removes the
+    // link itself, not its target.
+    check_symlink(file_path, follow_symlinks)?;
     
     // Get user confirmation
     print!("Are you sure you want to delete {}? (yes/no): ", filename);
@@ -208,7 +956,10 @@ fn delete_file(filename: &str) -> BackupResult<()> {
 fn main() {
     println!("SafeBackup - Secure File Backup Utility (Rust Edition)");
     println!("======================================================");
-    
+
+    // Opt-in flag: follow symlinks as long as they stay within the cwd.
+    let follow_symlinks = std::env::args().any(|arg| arg == "--follow-symlinks");
+
     // Log session start
     if let Err(e) = log_action("SafeBackup session started") {
         eprintln!("Warning: Could not write to log file: {}", e);
@@ -231,7 +982,7 @@ fn main() {
     }
     
     // Get command from user
-    print!("Please enter your command (backup, restore, delete): ");
+    print!("Please enter your command (backup, restore, verify, delete): ");
     io::stdout().flush().expect("Failed to flush stdout");
     
     let mut command = String::new();
@@ -241,9 +992,22 @@ fn main() {
     
     // Execute command
     let result = match command.as_str() {
-        "backup" => backup_file(filename),
-        "restore" => restore_file(filename),
-        "delete" => delete_file(filename),
+        "backup" => {
+            if Path::new(filename).is_dir() {
+                backup_dir(filename, follow_symlinks)
+            } else {
+                backup_file(filename, follow_symlinks)
+            }
+        }
+        "restore" => {
+            if Path::new(&format!("{}.bak.manifest", filename)).exists() {
+                restore_dir(filename)
+            } else {
+                restore_file(filename)
+            }
+        }
+        "verify" => verify_file(filename),
+        "delete" => delete_file(filename, follow_symlinks),
         _ => {
             eprintln!("Unknown command: {}", command);
             let _ = log_action(&format!("Unknown command attempted: {}", command));
@@ -293,4 +1057,117 @@ mod tests {
         assert!(validate_filename("file&command").is_err());
         assert!(validate_filename("file|pipe").is_err());
     }
+
+    #[test]
+    fn test_sha256_known_vectors() {
+        let mut empty = sha256::Sha256::new();
+        empty.update(b"");
+        assert_eq!(
+            sha256::to_hex(&empty.finalize()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let mut abc = sha256::Sha256::new();
+        abc.update(b"abc");
+        assert_eq!(
+            sha256::to_hex(&abc.finalize()),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    /// Best-effort chmod a tree back to writable so a test can delete a
+    /// fixture that intentionally contains read-only dirs/files.
+    #[cfg(unix)]
+    fn chmod_tree_writable(root: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let top = Path::new(root);
+        if !top.exists() {
+            return;
+        }
+        let mut files = Vec::new();
+        let mut stack = vec![top.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let _ = fs::set_permissions(&dir, fs::Permissions::from_mode(0o755));
+            if let Ok(rd) = fs::read_dir(&dir) {
+                for entry in rd.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        stack.push(path);
+                    } else {
+                        files.push(path);
+                    }
+                }
+            }
+        }
+        for path in files {
+            let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o644));
+        }
+    }
+
+    #[cfg(unix)]
+    fn cleanup_dir_fixture(root: &str) {
+        chmod_tree_writable(root);
+        chmod_tree_writable(&format!("{}.bak", root));
+        let _ = fs::remove_dir_all(root);
+        let _ = fs::remove_dir_all(format!("{}.bak", root));
+        let _ = fs::remove_file(format!("{}.bak.manifest", root));
+        let _ = fs::remove_file(format!("{}.lock", root));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_backup_restore_roundtrip_readonly() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = "sb_rt_tree";
+        cleanup_dir_fixture(root);
+
+        fs::create_dir_all(format!("{}/sub", root)).unwrap();
+        fs::write(format!("{}/top.txt", root), b"top").unwrap();
+        fs::write(format!("{}/sub/inner.txt", root), b"inner").unwrap();
+        // A read-only file and a read-only directory: the exact modes that
+        // exposed the two EACCES bugs in backup_dir/restore_dir.
+        fs::set_permissions(format!("{}/sub/inner.txt", root), fs::Permissions::from_mode(0o444)).unwrap();
+        fs::set_permissions(format!("{}/sub", root), fs::Permissions::from_mode(0o555)).unwrap();
+
+        backup_dir(root, false).unwrap();
+
+        // Wipe the original and reconstruct it from the mirror.
+        chmod_tree_writable(root);
+        fs::remove_dir_all(root).unwrap();
+        restore_dir(root).unwrap();
+
+        assert_eq!(fs::read(format!("{}/top.txt", root)).unwrap(), b"top");
+        assert_eq!(fs::read(format!("{}/sub/inner.txt", root)).unwrap(), b"inner");
+        let sub_mode = fs::metadata(format!("{}/sub", root)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(sub_mode, 0o555);
+        let file_mode =
+            fs::metadata(format!("{}/sub/inner.txt", root)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(file_mode, 0o444);
+
+        cleanup_dir_fixture(root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_symlink_rejects_link() {
+        use std::os::unix::fs::symlink;
+
+        let target = "sb_sym_target.txt";
+        let link = "sb_sym_link.txt";
+        let _ = fs::remove_file(link);
+        let _ = fs::remove_file(target);
+        fs::write(target, b"data").unwrap();
+        symlink(target, link).unwrap();
+
+        match check_symlink(Path::new(link), false) {
+            Err(BackupError::SymlinkRejected(_)) => {}
+            other => panic!("expected SymlinkRejected, got {:?}", other),
+        }
+        // A plain regular file passes the check.
+        assert!(check_symlink(Path::new(target), false).is_ok());
+
+        let _ = fs::remove_file(link);
+        let _ = fs::remove_file(target);
+    }
 }